@@ -1,7 +1,7 @@
 //! A Rust look-alike for Peekable that allows peeping into immutable references.
 
-use std::iter::Iterator;
-use std::mem;
+use std::collections::VecDeque;
+use std::iter::{FusedIterator, Iterator};
 
 /// peepable is a Rust look-alike for
 /// [`Peekable`](https://doc.rust-lang.org/std/iter/struct.Peekable.html).
@@ -43,24 +43,140 @@ where
     /// The underlying iterator for the Peepable.
     iter: I,
 
-    /// The next item in the iterator. Because we're eager, this will
-    /// always have a value. Peeking returns a reference to this value,
-    /// and next shifts this off and replaces it with a new value.
-    next: Option<I::Item>,
+    /// The buffered lookahead window. Because we're eager, this holds up to
+    /// `lookahead` upcoming items (fewer once the underlying iterator runs
+    /// dry). `peep_nth(0)`/`next()` operate on the front of this buffer.
+    buffer: VecDeque<I::Item>,
+
+    /// The configured window size from `with_lookahead`. An empty `buffer`
+    /// only means the source is exhausted when `lookahead > 0`; with a
+    /// `lookahead` of `0` the buffer is always empty by design, so `next()`
+    /// must pull straight from `iter` instead of treating that as the end.
+    lookahead: usize,
+
+    /// The most recently yielded item, cached for backward-looking access.
+    /// Only maintained by `next_tracked` (which requires `I::Item: Clone`);
+    /// plain `Iterator::next` calls don't touch it. See `prev`/`prev_peep`.
+    prev: Option<I::Item>,
 }
 
 impl<I: Iterator> Iterator for Peepable<I> {
     type Item = I::Item;
 
+    /// Advances the iterator.
+    ///
+    /// Note: this (and any combinator built on it, like `for` loops or
+    /// `.filter()`/`.map()`) does not update `prev`/`prev_peep` — it can't
+    /// be bound on `I::Item: Clone`. Use `next_tracked` instead when you
+    /// need `prev`/`prev_peep` to stay in sync.
     fn next(&mut self) -> Option<Self::Item> {
-        // Load the iterator's next value, swap it with the peeked one,
-        // and return the peeked value.
-        let mut next = self.iter.next();
+        if self.lookahead == 0 {
+            return self.iter.next();
+        }
 
-        mem::swap(&mut next, &mut self.next);
+        let next = self.buffer.pop_front();
+
+        if let Some(item) = self.iter.next() {
+            self.buffer.push_back(item);
+        }
 
         next
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.lookahead > 0 && self.buffer.is_empty() {
+            return (0, Some(0));
+        }
+
+        let buffered = self.buffer.len();
+        let (lower, upper) = self.iter.size_hint();
+
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+
+    fn count(self) -> usize {
+        self.buffer.len() + self.iter.count()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n < self.buffer.len() {
+            self.buffer.drain(..n);
+            let next = self.buffer.pop_front();
+
+            // Top the buffer back up to the configured window size; `next()`
+            // alone only refills one item, which would shrink the window by
+            // `n` every time this fast path is taken.
+            self.buffer
+                .extend(self.iter.by_ref().take(self.lookahead - self.buffer.len()));
+
+            return next;
+        }
+
+        // The whole buffer and the following `remaining` items from the
+        // underlying iterator are skipped; `self.iter.nth(remaining)` lands
+        // exactly on the nth item of the Peepable's logical sequence.
+        let remaining = n - self.buffer.len();
+        self.buffer.clear();
+
+        let item = self.iter.nth(remaining);
+
+        // Refill the buffer so the lookahead window invariant still holds.
+        self.buffer.extend(self.iter.by_ref().take(self.lookahead));
+
+        item
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        match self.iter.last() {
+            Some(last) => Some(last),
+            None => self.buffer.into_iter().last(),
+        }
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let acc = self.buffer.into_iter().fold(init, &mut f);
+
+        self.iter.fold(acc, f)
+    }
+}
+
+impl<I: Iterator> FusedIterator for Peepable<I> {}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for Peepable<I> {}
+
+// Note: std's `Peekable` also implements `TrustedLen`, but that trait is
+// unstable, so it can't be implemented here on stable Rust.
+impl<I: DoubleEndedIterator> DoubleEndedIterator for Peepable<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.iter.next_back() {
+            Some(item) => Some(item),
+            None => self.buffer.pop_back(),
+        }
+    }
+}
+
+/// A trait for iterators that eagerly buffer their next item, allowing it
+/// to be peeped from an immutable reference.
+///
+/// This lets downstream code write functions generic over "anything
+/// peepable" (e.g. `fn parse<P: Peep<Item = Token>>(src: &P)`) instead of
+/// hard-coding the concrete `Peepable` type, and leaves room for other
+/// eager look-ahead adapters to implement it too.
+pub trait Peep: Iterator {
+    /// Peeps into the iterator, giving a reference to the next item.
+    fn peep(&self) -> Option<&Self::Item>;
+}
+
+impl<I: Iterator> Peep for Peepable<I> {
+    fn peep(&self) -> Option<&Self::Item> {
+        self.peep_nth(0)
+    }
 }
 
 impl<I: Iterator> Peepable<I> {
@@ -75,19 +191,52 @@ impl<I: Iterator> Peepable<I> {
     ///
     /// let peeper = Peepable::new(iter);
     /// ```
-    pub fn new(mut iter: I) -> Peepable<I> {
-        let next = iter.next();
+    pub fn new(iter: I) -> Peepable<I> {
+        Self::with_lookahead(iter, 1)
+    }
+
+    /// Consumes a given Iterator into a Peepable<Iterator> with an `n`-element
+    /// lookahead window, eagerly filling the buffer with up to `n` items.
+    ///
+    /// `Peepable::new` is the `n = 1` special case of this constructor. A
+    /// larger window lets callers peep multiple items ahead via `peep_nth`
+    /// and `peep_n`, which is what LL(k) parsers and sliding-window scanning
+    /// need.
+    ///
+    /// ```
+    /// use peepable::Peepable;
+    ///
+    /// let iter = vec![1, 2, 3, 4].into_iter();
+    ///
+    /// let peeper = Peepable::with_lookahead(iter, 2);
+    ///
+    /// assert_eq!(peeper.peep_nth(0), Some(&1));
+    /// assert_eq!(peeper.peep_nth(1), Some(&2));
+    /// assert_eq!(peeper.peep_nth(2), None);
+    /// ```
+    pub fn with_lookahead(mut iter: I, n: usize) -> Peepable<I> {
+        let mut buffer = VecDeque::with_capacity(n);
+
+        for _ in 0..n {
+            match iter.next() {
+                Some(item) => buffer.push_back(item),
+                None => break,
+            }
+        }
 
         Peepable {
-            iter: iter,
-            next: next,
+            iter,
+            buffer,
+            lookahead: n,
+            prev: None,
         }
     }
 
     /// Peeps into the iterator, giving a reference to the next item.
     ///
     /// This only takes a reference (doesn't need mutable), and the given
-    /// reference to the next item is immutable.
+    /// reference to the next item is immutable. This is equivalent to
+    /// `peep_nth(0)`.
     ///
     /// ```
     /// use peepable::Peepable;
@@ -97,16 +246,168 @@ impl<I: Iterator> Peepable<I> {
     /// assert_eq!(iter.peep(), Some(&0));
     /// ```
     pub fn peep(&self) -> Option<&I::Item> {
-        match self.next {
-            Some(ref next) => Some(next),
-            None => None,
+        Peep::peep(self)
+    }
+
+    /// Peeps `k` elements ahead without advancing the iterator, where
+    /// `peep_nth(0)` is the next item that `next()` would yield.
+    ///
+    /// Only items within the lookahead window configured via
+    /// `with_lookahead` (or the single-item window from `new`) are
+    /// available; beyond that, this returns `None` even if the underlying
+    /// iterator has more items left.
+    ///
+    /// ```
+    /// use peepable::Peepable;
+    ///
+    /// let iter = vec![1, 2, 3].into_iter();
+    /// let peeper = Peepable::with_lookahead(iter, 3);
+    ///
+    /// assert_eq!(peeper.peep_nth(0), Some(&1));
+    /// assert_eq!(peeper.peep_nth(2), Some(&3));
+    /// ```
+    pub fn peep_nth(&self, k: usize) -> Option<&I::Item> {
+        self.buffer.get(k)
+    }
+
+    /// Returns an iterator over the first `k` buffered, upcoming items,
+    /// without advancing the Peepable.
+    ///
+    /// ```
+    /// use peepable::Peepable;
+    ///
+    /// let iter = vec![1, 2, 3].into_iter();
+    /// let peeper = Peepable::with_lookahead(iter, 3);
+    ///
+    /// let window: Vec<_> = peeper.peep_n(2).collect();
+    /// assert_eq!(window, vec![&1, &2]);
+    /// ```
+    pub fn peep_n(&self, k: usize) -> impl Iterator<Item = &I::Item> {
+        self.buffer.iter().take(k)
+    }
+
+    /// Gives a reference to the most recently yielded item, if any.
+    ///
+    /// This is the backward-looking counterpart to `peep`: where `peep`
+    /// looks one element ahead, `prev_peep` looks one element behind. It
+    /// returns `None` until the first call to `next_tracked()`.
+    ///
+    /// ```
+    /// use peepable::Peepable;
+    ///
+    /// let mut iter = Peepable::new(vec![1, 2, 3].into_iter());
+    ///
+    /// assert_eq!(iter.prev_peep(), None);
+    /// assert_eq!(iter.next_tracked(), Some(1));
+    /// assert_eq!(iter.prev_peep(), Some(&1));
+    /// ```
+    pub fn prev_peep(&self) -> Option<&I::Item> {
+        self.prev.as_ref()
+    }
+
+    /// Returns a clone of the most recently yielded item, if any.
+    ///
+    /// See `prev_peep` for a reference-returning version that doesn't
+    /// require `I::Item: Clone`.
+    pub fn prev(&self) -> Option<I::Item>
+    where
+        I::Item: Clone,
+    {
+        self.prev.clone()
+    }
+}
+
+impl<I: Iterator> Peepable<I>
+where
+    I::Item: Clone,
+{
+    /// Advances the Peepable like `Iterator::next`, additionally caching the
+    /// yielded item so `prev`/`prev_peep` can see it afterwards.
+    ///
+    /// `Iterator::next` itself can't do this caching, since it's implemented
+    /// unconditionally for any `I::Item` and has no way to clone into `prev`
+    /// when `I::Item` isn't `Clone`. Call `next_tracked` instead of `next`
+    /// when you want `prev`/`prev_peep` to stay in sync.
+    pub fn next_tracked(&mut self) -> Option<I::Item> {
+        let next = self.next();
+
+        if next.is_some() {
+            self.prev = next.clone();
         }
+
+        next
+    }
+}
+
+impl<I: Iterator> Peepable<I> {
+    /// Peeps into the iterator, giving a mutable reference to the next item.
+    ///
+    /// This lets callers rewrite the value that a following call to `next()`
+    /// will yield.
+    ///
+    /// ```
+    /// use peepable::Peepable;
+    ///
+    /// let mut iter = Peepable::new(vec![1, 2, 3].into_iter());
+    ///
+    /// if let Some(next) = iter.peep_mut() {
+    ///     *next = 5;
+    /// }
+    ///
+    /// assert_eq!(iter.next(), Some(5));
+    /// assert_eq!(iter.next(), Some(2));
+    /// ```
+    pub fn peep_mut(&mut self) -> Option<&mut I::Item> {
+        self.buffer.front_mut()
+    }
+
+    /// Consumes and returns the next item if a predicate matches it.
+    ///
+    /// If `func` returns `true` for the peeped item, this advances the
+    /// Peepable and returns that item, just like `next()` would. Otherwise,
+    /// the Peepable is left untouched and `None` is returned.
+    ///
+    /// ```
+    /// use peepable::Peepable;
+    ///
+    /// let mut iter = Peepable::new(vec![1, 2, 3].into_iter());
+    ///
+    /// assert_eq!(iter.next_if(|&x| x == 0), None);
+    /// assert_eq!(iter.next_if(|&x| x == 1), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// ```
+    pub fn next_if(&mut self, func: impl FnOnce(&I::Item) -> bool) -> Option<I::Item> {
+        match self.buffer.front() {
+            Some(item) if func(item) => self.next(),
+            _ => None,
+        }
+    }
+
+    /// Consumes and returns the next item if it is equal to `expected`.
+    ///
+    /// This is a convenience wrapper around `next_if` for the common case
+    /// of matching against a specific value.
+    ///
+    /// ```
+    /// use peepable::Peepable;
+    ///
+    /// let mut iter = Peepable::new(vec![1, 2, 3].into_iter());
+    ///
+    /// assert_eq!(iter.next_if_eq(&0), None);
+    /// assert_eq!(iter.next_if_eq(&1), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// ```
+    pub fn next_if_eq<T>(&mut self, expected: &T) -> Option<I::Item>
+    where
+        I::Item: PartialEq<T>,
+    {
+        self.next_if(|item| item == expected)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Peepable;
+    use super::{Peep, Peepable};
 
     #[test]
     fn basic_peeping() {
@@ -131,6 +432,22 @@ mod tests {
         assert_eq!(peeper.peep(), None);
     }
 
+    #[test]
+    fn works_with_non_clone_items() {
+        struct NotClone(i32);
+
+        let items = vec![NotClone(1), NotClone(2)];
+
+        let mut peeper = Peepable::new(items.into_iter());
+
+        assert_eq!(peeper.next().map(|item| item.0), Some(1));
+        assert_eq!(peeper.next().map(|item| item.0), Some(2));
+        assert!(peeper.next().is_none());
+
+        // Backward-looking access is simply unavailable for non-`Clone` items.
+        assert_eq!(peeper.prev_peep().map(|item| item.0), None);
+    }
+
     #[test]
     fn basic_iterator() {
         let vec = vec![1, 2, 3];
@@ -144,6 +461,202 @@ mod tests {
         assert_eq!(peepable.next(), None);
     }
 
+    #[test]
+    fn size_hint_and_len_account_for_buffered_item() {
+        let vec = vec![1, 2, 3];
+
+        let mut peeper = Peepable::new(vec.into_iter());
+
+        assert_eq!(peeper.size_hint(), (3, Some(3)));
+        assert_eq!(peeper.len(), 3);
+
+        peeper.next();
+
+        assert_eq!(peeper.size_hint(), (2, Some(2)));
+        assert_eq!(peeper.len(), 2);
+
+        peeper.next();
+        peeper.next();
+
+        assert_eq!(peeper.size_hint(), (0, Some(0)));
+        assert_eq!(peeper.len(), 0);
+    }
+
+    #[test]
+    fn nth_last_and_fold_account_for_buffered_item() {
+        let vec = vec![1, 2, 3, 4, 5];
+
+        let mut peeper = Peepable::new(vec.into_iter());
+
+        assert_eq!(peeper.nth(2), Some(3));
+        assert_eq!(peeper.peep(), Some(&4));
+
+        let vec = vec![1, 2, 3];
+        let peeper = Peepable::new(vec.into_iter());
+        assert_eq!(peeper.last(), Some(3));
+
+        let vec = vec![1, 2, 3];
+        let peeper = Peepable::new(vec.into_iter());
+        let sum = peeper.fold(0, |acc, x| acc + x);
+        assert_eq!(sum, 6);
+    }
+
+    #[test]
+    fn double_ended_yields_buffered_item_last() {
+        let vec = vec![1, 2, 3];
+
+        let mut peeper = Peepable::new(vec.into_iter());
+
+        assert_eq!(peeper.next_back(), Some(3));
+        assert_eq!(peeper.next_back(), Some(2));
+        assert_eq!(peeper.next_back(), Some(1));
+        assert_eq!(peeper.next_back(), None);
+    }
+
+    #[test]
+    fn with_lookahead_peeps_multiple_elements() {
+        let vec = vec![1, 2, 3, 4];
+
+        let mut peeper = Peepable::with_lookahead(vec.into_iter(), 2);
+
+        assert_eq!(peeper.peep_nth(0), Some(&1));
+        assert_eq!(peeper.peep_nth(1), Some(&2));
+        assert_eq!(peeper.peep_nth(2), None);
+
+        assert_eq!(peeper.peep_n(2).collect::<Vec<_>>(), vec![&1, &2]);
+
+        assert_eq!(peeper.next(), Some(1));
+        assert_eq!(peeper.peep_nth(0), Some(&2));
+        assert_eq!(peeper.peep_nth(1), Some(&3));
+
+        assert_eq!(peeper.next(), Some(2));
+        assert_eq!(peeper.next(), Some(3));
+        assert_eq!(peeper.peep_nth(1), None);
+        assert_eq!(peeper.next(), Some(4));
+        assert_eq!(peeper.next(), None);
+    }
+
+    #[test]
+    fn nth_restores_lookahead_window() {
+        let vec = vec![1, 2, 3, 4, 5, 6, 7];
+
+        let mut peeper = Peepable::with_lookahead(vec.into_iter(), 3);
+
+        assert_eq!(peeper.nth(2), Some(3));
+
+        // The window should still be full, not shrunk by the skipped count.
+        assert_eq!(peeper.peep_nth(0), Some(&4));
+        assert_eq!(peeper.peep_nth(1), Some(&5));
+        assert_eq!(peeper.peep_nth(2), Some(&6));
+    }
+
+    #[test]
+    fn zero_lookahead_does_not_desync_next() {
+        let vec = vec![1, 2, 3];
+
+        let mut peeper = Peepable::with_lookahead(vec.into_iter(), 0);
+
+        assert_eq!(peeper.peep_nth(0), None);
+        assert_eq!(peeper.next(), Some(1));
+        assert_eq!(peeper.next(), Some(2));
+        assert_eq!(peeper.next(), Some(3));
+        assert_eq!(peeper.next(), None);
+    }
+
+    #[test]
+    fn one_lookahead_matches_new() {
+        let via_new = Peepable::new(vec![1, 2, 3].into_iter()).collect::<Vec<_>>();
+        let via_lookahead = Peepable::with_lookahead(vec![1, 2, 3].into_iter(), 1).collect::<Vec<_>>();
+
+        assert_eq!(via_new, via_lookahead);
+    }
+
+    #[test]
+    fn peep_trait_is_generic_over_wrapper() {
+        fn first<P: Peep<Item = i32>>(src: &P) -> Option<i32> {
+            src.peep().copied()
+        }
+
+        let vec = vec![1, 2, 3];
+        let peeper = Peepable::new(vec.into_iter());
+
+        assert_eq!(first(&peeper), Some(1));
+    }
+
+    #[test]
+    fn prev_tracks_last_yielded_item() {
+        let vec = vec![1, 2, 3];
+
+        let mut peeper = Peepable::new(vec.into_iter());
+
+        assert_eq!(peeper.prev_peep(), None);
+        assert_eq!(peeper.prev(), None);
+
+        assert_eq!(peeper.next_tracked(), Some(1));
+        assert_eq!(peeper.prev_peep(), Some(&1));
+        assert_eq!(peeper.prev(), Some(1));
+
+        assert_eq!(peeper.next_tracked(), Some(2));
+        assert_eq!(peeper.prev_peep(), Some(&2));
+
+        assert_eq!(peeper.next_tracked(), Some(3));
+        assert_eq!(peeper.next_tracked(), None);
+        assert_eq!(peeper.prev_peep(), Some(&3));
+    }
+
+    #[test]
+    fn next_does_not_update_prev() {
+        let vec = vec![1, 2, 3];
+
+        let mut peeper = Peepable::new(vec.into_iter());
+
+        assert_eq!(Iterator::next(&mut peeper), Some(1));
+        assert_eq!(peeper.prev_peep(), None);
+    }
+
+    #[test]
+    fn peep_mut_rewrites_next_item() {
+        let vec = vec![1, 2, 3];
+
+        let mut peeper = Peepable::new(vec.into_iter());
+
+        if let Some(next) = peeper.peep_mut() {
+            *next = 10;
+        }
+
+        assert_eq!(peeper.next(), Some(10));
+        assert_eq!(peeper.next(), Some(2));
+    }
+
+    #[test]
+    fn next_if_consumes_only_on_match() {
+        let vec = vec![1, 2, 3];
+
+        let mut peeper = Peepable::new(vec.into_iter());
+
+        assert_eq!(peeper.next_if(|&x| x == 2), None);
+        assert_eq!(peeper.peep(), Some(&1));
+
+        assert_eq!(peeper.next_if(|&x| x == 1), Some(1));
+        assert_eq!(peeper.peep(), Some(&2));
+
+        assert_eq!(peeper.next(), Some(2));
+        assert_eq!(peeper.next(), Some(3));
+        assert_eq!(peeper.next_if(|_| true), None);
+    }
+
+    #[test]
+    fn next_if_eq_consumes_only_on_match() {
+        let vec = vec![1, 2, 3];
+
+        let mut peeper = Peepable::new(vec.into_iter());
+
+        assert_eq!(peeper.next_if_eq(&2), None);
+        assert_eq!(peeper.next_if_eq(&1), Some(1));
+        assert_eq!(peeper.next_if_eq(&2), Some(2));
+        assert_eq!(peeper.next(), Some(3));
+    }
+
     #[test]
     fn has_iterator_tools() {
         let iter = Peepable::new((0..4));